@@ -0,0 +1,232 @@
+use crate::document::model::*;
+use crate::document::providers::DocumentProvider;
+use std::error::Error;
+use std::num::NonZeroU32;
+
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b'\t', b';', b'|'];
+const SNIFF_RECORDS: usize = 5;
+
+pub struct CsvProvider;
+
+impl CsvProvider {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+impl DocumentProvider for CsvProvider {
+  fn parse_buffer(&self, data: &[u8]) -> Result<Document, Box<dyn Error + Send + Sync>> {
+    let delimiter = sniff_delimiter(data);
+
+    let mut reader = csv::ReaderBuilder::new()
+      .delimiter(delimiter)
+      .has_headers(false)
+      .flexible(true)
+      .from_reader(data);
+
+    let mut raw_rows: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+      let record = record?;
+      raw_rows.push(record.iter().map(|field| field.to_string()).collect());
+    }
+
+    let blocks = match parse_rows_to_table(&raw_rows) {
+      Some(table) => vec![Block::Table(table)],
+      None => Vec::new(),
+    };
+
+    Ok(Document {
+      blocks,
+      metadata: DocumentMetadata::default(),
+      notes: Vec::new(),
+      comments: Vec::new(),
+    })
+  }
+
+  fn name(&self) -> &'static str {
+    "csv"
+  }
+}
+
+/// Pick the delimiter whose record field-count is most consistent across the
+/// first few records, defaulting to `,` when no candidate is found.
+fn sniff_delimiter(data: &[u8]) -> u8 {
+  // `max_by_key` keeps the *last* element seen among ties, so iterate in
+  // reverse order: that makes `,` (first in CANDIDATE_DELIMITERS) win ties,
+  // matching the "defaults to `,`" behavior documented above.
+  CANDIDATE_DELIMITERS
+    .iter()
+    .copied()
+    .rev()
+    .max_by_key(|&delim| delimiter_consistency(data, delim))
+    .unwrap_or(b',')
+}
+
+/// Higher is better: rewards delimiters that split the first few records into
+/// a consistent, non-trivial field count.
+///
+/// Parses with the `csv` crate itself, rather than a raw `str::lines()` split,
+/// so a quoted field's embedded newlines or embedded delimiter characters
+/// don't get mistaken for extra record/field boundaries while sniffing.
+fn delimiter_consistency(data: &[u8], delimiter: u8) -> i64 {
+  let mut reader = csv::ReaderBuilder::new()
+    .delimiter(delimiter)
+    .has_headers(false)
+    .flexible(true)
+    .from_reader(data);
+
+  let counts: Vec<usize> = reader
+    .records()
+    .take(SNIFF_RECORDS)
+    .filter_map(|record| record.ok())
+    .map(|record| record.len())
+    .collect();
+
+  // A field count of 1 means this delimiter never actually split anything.
+  if counts.is_empty() || counts.iter().any(|&count| count <= 1) {
+    return 0;
+  }
+
+  let first = counts[0] as i64;
+  let variance: i64 = counts
+    .iter()
+    .map(|&count| (count as i64 - first).abs())
+    .sum();
+
+  first * 100 - variance
+}
+
+fn parse_rows_to_table(rows: &[Vec<String>]) -> Option<Table> {
+  let mut table_rows: Vec<TableRow> = Vec::new();
+  let mut is_first_row = true;
+
+  for row in rows {
+    let cells: Vec<TableCell> = row
+      .iter()
+      .map(|field| {
+        let paragraph = Paragraph {
+          kind: ParagraphKind::Normal,
+          inlines: vec![Inline::Text(field.clone())],
+        };
+        TableCell {
+          blocks: vec![Block::Paragraph(paragraph)],
+          colspan: NonZeroU32::new(1).unwrap(),
+          rowspan: NonZeroU32::new(1).unwrap(),
+        }
+      })
+      .collect();
+
+    if !cells.is_empty() {
+      let kind = if is_first_row && row_contains_text(row) {
+        TableRowKind::Header
+      } else {
+        TableRowKind::Body
+      };
+      is_first_row = false;
+
+      table_rows.push(TableRow { cells, kind });
+    }
+  }
+
+  if table_rows.is_empty() {
+    None
+  } else {
+    Some(Table {
+      rows: table_rows,
+      col_widths: None,
+    })
+  }
+}
+
+fn row_contains_text(row: &[String]) -> bool {
+  row.iter().any(|field| field.parse::<f64>().is_err() && !field.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_sniff_delimiter_comma() {
+    let data = b"a,b,c\n1,2,3\n4,5,6\n";
+    assert_eq!(sniff_delimiter(data), b',');
+  }
+
+  #[test]
+  fn test_sniff_delimiter_tab() {
+    let data = b"a\tb\tc\n1\t2\t3\n4\t5\t6\n";
+    assert_eq!(sniff_delimiter(data), b'\t');
+  }
+
+  #[test]
+  fn test_sniff_delimiter_semicolon() {
+    let data = b"a;b;c\n1;2;3\n";
+    assert_eq!(sniff_delimiter(data), b';');
+  }
+
+  #[test]
+  fn test_row_contains_text() {
+    assert!(row_contains_text(&["Header".to_string(), "42".to_string()]));
+    assert!(!row_contains_text(&["42".to_string(), "3.14".to_string()]));
+  }
+
+  #[test]
+  fn test_sniff_delimiter_ties_default_to_comma() {
+    // No candidate delimiter appears at all, so every consistency score is 0.
+    let data = b"abc\ndef\nghi\n";
+    assert_eq!(sniff_delimiter(data), b',');
+  }
+
+  fn cell_text(cell: &TableCell) -> &str {
+    match &cell.blocks[0] {
+      Block::Paragraph(p) => match &p.inlines[0] {
+        Inline::Text(t) => t,
+        _ => panic!("expected a text inline"),
+      },
+      _ => panic!("expected a paragraph block"),
+    }
+  }
+
+  #[test]
+  fn test_parse_rows_to_table_marks_header_and_body() {
+    let rows = vec![
+      vec!["Name".to_string(), "Age".to_string()],
+      vec!["Alice".to_string(), "30".to_string()],
+    ];
+    let table = parse_rows_to_table(&rows).unwrap();
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].kind, TableRowKind::Header);
+    assert_eq!(table.rows[1].kind, TableRowKind::Body);
+    assert_eq!(cell_text(&table.rows[1].cells[0]), "Alice");
+  }
+
+  #[test]
+  fn test_sniff_delimiter_ignores_embedded_newline_inside_quoted_field() {
+    // A naive `str::lines()` split would see the quoted field's embedded
+    // newline as a line break, making the true delimiter (`;`) look
+    // inconsistent and falling back to the tie-break default (`,`) instead.
+    let data = b"Name;Bio\n\"Alice\";\"Likes; semicolons\nand new lines\"\nBob;Plain\n";
+    assert_eq!(sniff_delimiter(data), b';');
+  }
+
+  #[test]
+  fn test_parse_buffer_handles_quoted_field_with_embedded_newline_and_delimiter() {
+    // RFC 4180: a quoted field may contain the delimiter and literal newlines.
+    let data = b"Name,Bio\n\"Alice\",\"Likes, commas\nand new lines\"\nBob,Plain\n";
+
+    let document = CsvProvider::new().parse_buffer(data).unwrap();
+    assert_eq!(document.blocks.len(), 1);
+    let table = match &document.blocks[0] {
+      Block::Table(t) => t,
+      _ => panic!("expected a Table block"),
+    };
+
+    assert_eq!(table.rows.len(), 3);
+    assert_eq!(table.rows[0].kind, TableRowKind::Header);
+    assert_eq!(
+      cell_text(&table.rows[1].cells[1]),
+      "Likes, commas\nand new lines"
+    );
+    assert_eq!(cell_text(&table.rows[2].cells[0]), "Bob");
+  }
+}
@@ -1,15 +1,36 @@
 use crate::document::model::*;
 use crate::document::providers::DocumentProvider;
 use calamine::{open_workbook_auto_from_rs, Data, Range, Reader};
+use std::collections::HashSet;
 use std::error::Error;
 use std::io::Cursor;
 use std::num::NonZeroU32;
 
-pub struct ExcelProvider;
+pub struct ExcelProvider {
+  sheets: Option<Vec<String>>,
+  show_formulas: bool,
+}
 
 impl ExcelProvider {
   pub fn new() -> Self {
-    Self
+    Self {
+      sheets: None,
+      show_formulas: false,
+    }
+  }
+
+  /// Restrict parsing to the given sheet names, skipping all others.
+  pub fn with_sheets(sheets: Vec<String>) -> Self {
+    Self {
+      sheets: Some(sheets),
+      show_formulas: false,
+    }
+  }
+
+  /// Emit formula text alongside evaluated values for cells backed by a formula.
+  pub fn with_formulas(mut self, show_formulas: bool) -> Self {
+    self.show_formulas = show_formulas;
+    self
   }
 }
 
@@ -19,17 +40,35 @@ impl DocumentProvider for ExcelProvider {
     let mut workbook = open_workbook_auto_from_rs(cursor)?;
 
     let mut blocks: Vec<Block> = Vec::new();
+    let mut parsed_sheet_names: Vec<String> = Vec::new();
     let sheet_names = workbook.sheet_names();
 
     for sheet_name in sheet_names {
+      if !sheet_is_selected(&self.sheets, &sheet_name) {
+        continue;
+      }
+
       if let Ok(range) = workbook.worksheet_range(&sheet_name) {
-        if let Some(table) = parse_sheet_to_table(&range) {
+        let merges = workbook
+          .worksheet_merge_cells(&sheet_name)
+          .unwrap_or_default();
+        let formulas = if self.show_formulas {
+          workbook.worksheet_formula(&sheet_name).ok()
+        } else {
+          None
+        };
+        if let Some(table) = parse_sheet_to_table(&range, &merges, formulas.as_ref()) {
+          blocks.push(Block::Paragraph(sheet_heading(&sheet_name)));
           blocks.push(Block::Table(table));
+          parsed_sheet_names.push(sheet_name);
         }
       }
     }
 
-    let metadata = DocumentMetadata::default();
+    let metadata = DocumentMetadata {
+      sheet_names: parsed_sheet_names,
+      ..Default::default()
+    };
 
     Ok(Document {
       blocks,
@@ -44,27 +83,91 @@ impl DocumentProvider for ExcelProvider {
   }
 }
 
-fn parse_sheet_to_table(range: &Range<Data>) -> Option<Table> {
+/// Whether a sheet should be parsed: every sheet is selected when
+/// `ExcelProvider::with_sheets` wasn't used, otherwise only the named ones.
+fn sheet_is_selected(selected: &Option<Vec<String>>, sheet_name: &str) -> bool {
+  match selected {
+    Some(names) => names.iter().any(|name| name == sheet_name),
+    None => true,
+  }
+}
+
+/// Merge regions come from untrusted workbook bytes. Reject a region whose
+/// `end < start` (would underflow the colspan/rowspan arithmetic below) or
+/// whose `end` falls outside the sheet's actual extent (would otherwise walk
+/// a region far larger than any real worksheet, e.g. billions of rows/cols).
+fn is_valid_region(region: calamine::Dimensions, sheet_end: (u32, u32)) -> bool {
+  let (start, end) = region;
+  end.0 >= start.0 && end.1 >= start.1 && end.0 <= sheet_end.0 && end.1 <= sheet_end.1
+}
+
+fn sheet_heading(sheet_name: &str) -> Paragraph {
+  Paragraph {
+    kind: ParagraphKind::Heading(1),
+    inlines: vec![Inline::Text(sheet_name.to_string())],
+  }
+}
+
+fn parse_sheet_to_table(
+  range: &Range<Data>,
+  merges: &[calamine::Dimensions],
+  formulas: Option<&Range<String>>,
+) -> Option<Table> {
   let rows = range.rows();
   let mut table_rows: Vec<TableRow> = Vec::new();
   let mut is_first_row = true;
+  let mut occupied: HashSet<(u32, u32)> = HashSet::new();
+  // Bound merge regions against the sheet's real extent: merges come straight
+  // from untrusted workbook bytes, so a crafted region spanning e.g.
+  // (0,0)..(2_000_000_000, 2_000_000_000) must not reach the occupancy loop
+  // below, which would otherwise attempt ~10^18 HashSet insertions.
+  let sheet_end = range.end().unwrap_or((0, 0));
 
-  for row in rows {
-    let cells: Vec<TableCell> = row
-      .iter()
-      .map(|cell| {
-        let text = data_to_string(cell);
-        let paragraph = Paragraph {
-          kind: ParagraphKind::Normal,
-          inlines: vec![Inline::Text(text)],
-        };
-        TableCell {
-          blocks: vec![Block::Paragraph(paragraph)],
-          colspan: NonZeroU32::new(1).unwrap(),
-          rowspan: NonZeroU32::new(1).unwrap(),
+  for (row_idx, row) in rows.enumerate() {
+    let row_idx = row_idx as u32;
+    let mut cells: Vec<TableCell> = Vec::new();
+
+    for (col_idx, cell) in row.iter().enumerate() {
+      let col_idx = col_idx as u32;
+      if occupied.contains(&(row_idx, col_idx)) {
+        continue;
+      }
+
+      let region = merges.iter().find(|region| {
+        region.0 == (row_idx, col_idx) && is_valid_region(**region, sheet_end)
+      });
+
+      let (colspan, rowspan) = if let Some(region) = region {
+        let (start, end) = *region;
+        for r in start.0..=end.0 {
+          for c in start.1..=end.1 {
+            if (r, c) != (row_idx, col_idx) {
+              occupied.insert((r, c));
+            }
+          }
         }
-      })
-      .collect();
+        (end.1 - start.1 + 1, end.0 - start.0 + 1)
+      } else {
+        (1, 1)
+      };
+
+      let text = data_to_string(cell);
+      let mut inlines = vec![Inline::Text(text)];
+      if let Some(formula) = formulas.and_then(|f| f.get((row_idx as usize, col_idx as usize))) {
+        if !formula.is_empty() {
+          inlines.push(Inline::Code(format!("={formula}")));
+        }
+      }
+      let paragraph = Paragraph {
+        kind: ParagraphKind::Normal,
+        inlines,
+      };
+      cells.push(TableCell {
+        blocks: vec![Block::Paragraph(paragraph)],
+        colspan: NonZeroU32::new(colspan).unwrap(),
+        rowspan: NonZeroU32::new(rowspan).unwrap(),
+      });
+    }
 
     if !cells.is_empty() {
       let kind = if is_first_row && row_contains_text(row) {
@@ -82,10 +185,70 @@ fn parse_sheet_to_table(range: &Range<Data>) -> Option<Table> {
   if table_rows.is_empty() {
     None
   } else {
-    Some(Table { rows: table_rows })
+    // `Table.col_widths` is documented as the worksheet's authored column
+    // widths. calamine's generic `Reader` trait (the one
+    // `open_workbook_auto_from_rs` gives us) doesn't expose that `<cols>`
+    // metadata, only cell values, so there's no honest way to populate this
+    // field from content alone without mislabeling a heuristic as real
+    // layout data (e.g. a narrow authored column full of long text, or a wide
+    // authored column of short numbers, would both report the wrong width).
+    // Leave it unset rather than ship a guess under the authored-width name;
+    // see `content_based_col_width_hint` for the shelved heuristic pending a
+    // product decision on whether callers want that instead.
+    Some(Table {
+      rows: table_rows,
+      col_widths: None,
+    })
   }
 }
 
+/// Estimate relative column widths from the widest rendered value per column.
+///
+/// Not wired into `Table.col_widths`: that field is documented as the
+/// worksheet's *authored* widths, and this is a content heuristic that can be
+/// arbitrarily wrong relative to the real layout (e.g. a narrow authored
+/// column full of long text, or a wide authored column of short numbers).
+/// Kept here, unused, as a documented starting point if a caller decides a
+/// content-based hint is an acceptable substitute — that's a product call,
+/// not one to make silently in this provider.
+#[allow(dead_code)]
+fn content_based_col_width_hint(range: &Range<Data>) -> Option<Vec<u32>> {
+  let width = range.width();
+  if width == 0 {
+    return None;
+  }
+
+  let mut max_lens = vec![0usize; width];
+  for row in range.rows() {
+    for (col_idx, cell) in row.iter().enumerate() {
+      let len = data_to_string(cell).chars().count();
+      if len > max_lens[col_idx] {
+        max_lens[col_idx] = len;
+      }
+    }
+  }
+
+  let total: usize = max_lens.iter().sum();
+  if total == 0 {
+    return None;
+  }
+
+  let mut widths: Vec<u32> = max_lens
+    .iter()
+    .map(|len| ((*len as f64 / total as f64) * 100.0).round() as u32)
+    .collect();
+
+  // Rounding can drift the total away from 100; nudge the largest column to absorb it.
+  let drift = 100 - widths.iter().sum::<u32>() as i32;
+  if drift != 0 {
+    if let Some(max_idx) = (0..widths.len()).max_by_key(|&i| widths[i]) {
+      widths[max_idx] = (widths[max_idx] as i32 + drift).max(0) as u32;
+    }
+  }
+
+  Some(widths)
+}
+
 fn data_to_string(cell: &Data) -> String {
   match cell {
     Data::Int(i) => i.to_string(),
@@ -128,4 +291,199 @@ mod tests {
     let empty_row: Vec<Data> = vec![];
     assert!(!row_contains_text(&empty_row));
   }
+
+  #[test]
+  fn test_is_valid_region() {
+    let sheet_end = (9, 9);
+    assert!(is_valid_region(((0, 0), (1, 1)), sheet_end));
+    assert!(is_valid_region(((2, 2), (2, 2)), sheet_end));
+    assert!(!is_valid_region(((2, 2), (1, 1)), sheet_end));
+    assert!(!is_valid_region(((0, 5), (0, 4)), sheet_end));
+  }
+
+  #[test]
+  fn test_is_valid_region_rejects_region_past_sheet_bounds() {
+    // A crafted region spanning far past the sheet's real extent must be
+    // rejected before it ever reaches the occupancy-marking loop.
+    let sheet_end = (9, 9);
+    assert!(!is_valid_region(((0, 0), (2_000_000_000, 2_000_000_000)), sheet_end));
+    assert!(!is_valid_region(((0, 0), (10, 9)), sheet_end));
+    assert!(is_valid_region(((0, 0), (9, 9)), sheet_end));
+  }
+
+  fn range_from_rows(rows: Vec<Vec<Data>>) -> Range<Data> {
+    let height = rows.len() as u32;
+    let width = rows.first().map(|r| r.len()).unwrap_or(0) as u32;
+    let mut range = Range::new((0, 0), (height.saturating_sub(1), width.saturating_sub(1)));
+    for (r, row) in rows.into_iter().enumerate() {
+      for (c, value) in row.into_iter().enumerate() {
+        range.set_value((r as u32, c as u32), value);
+      }
+    }
+    range
+  }
+
+  fn formula_range_from_rows(rows: Vec<Vec<String>>) -> Range<String> {
+    let height = rows.len() as u32;
+    let width = rows.first().map(|r| r.len()).unwrap_or(0) as u32;
+    let mut range = Range::new((0, 0), (height.saturating_sub(1), width.saturating_sub(1)));
+    for (r, row) in rows.into_iter().enumerate() {
+      for (c, value) in row.into_iter().enumerate() {
+        range.set_value((r as u32, c as u32), value);
+      }
+    }
+    range
+  }
+
+  #[test]
+  fn test_formula_cell_emits_code_inline_alongside_value() {
+    let range = range_from_rows(vec![vec![Data::Int(55)]]);
+    let formulas = formula_range_from_rows(vec![vec!["SUM(A1:A10)".to_string()]]);
+
+    let table = parse_sheet_to_table(&range, &[], Some(&formulas)).unwrap();
+    let cell = &table.rows[0].cells[0];
+    let paragraph = match &cell.blocks[0] {
+      Block::Paragraph(p) => p,
+      _ => panic!("expected a paragraph block"),
+    };
+    assert_eq!(
+      paragraph.inlines,
+      vec![
+        Inline::Text("55".to_string()),
+        Inline::Code("=SUM(A1:A10)".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_no_formula_is_not_code_inline() {
+    let range = range_from_rows(vec![vec![Data::Int(55)]]);
+    let formulas = formula_range_from_rows(vec![vec![String::new()]]);
+
+    let table = parse_sheet_to_table(&range, &[], Some(&formulas)).unwrap();
+    let cell = &table.rows[0].cells[0];
+    let paragraph = match &cell.blocks[0] {
+      Block::Paragraph(p) => p,
+      _ => panic!("expected a paragraph block"),
+    };
+    assert_eq!(paragraph.inlines, vec![Inline::Text("55".to_string())]);
+  }
+
+  #[test]
+  fn test_merge_region_applies_colspan_and_rowspan() {
+    let range = range_from_rows(vec![
+      vec![Data::String("A".into()), Data::Empty, Data::String("C".into())],
+      vec![Data::Int(1), Data::Empty, Data::Int(3)],
+    ]);
+    let merges = vec![((0u32, 0u32), (0u32, 1u32))];
+
+    let table = parse_sheet_to_table(&range, &merges, None).unwrap();
+    let header = &table.rows[0];
+    assert_eq!(header.cells.len(), 2);
+    assert_eq!(header.cells[0].colspan.get(), 2);
+    assert_eq!(header.cells[0].rowspan.get(), 1);
+  }
+
+  #[test]
+  fn test_parsed_table_never_fabricates_col_widths() {
+    // `col_widths` is documented as the worksheet's authored widths, which we
+    // have no honest way to read through calamine's generic Reader trait, so
+    // it must stay unset rather than carry a content-based guess.
+    let range = range_from_rows(vec![vec![
+      Data::String("Name".into()),
+      Data::String("A very long description column".into()),
+    ]]);
+    let table = parse_sheet_to_table(&range, &[], None).unwrap();
+    assert_eq!(table.col_widths, None);
+  }
+
+  #[test]
+  fn test_sheet_is_selected_with_no_restriction() {
+    assert!(sheet_is_selected(&None, "Sheet1"));
+    assert!(sheet_is_selected(&None, "Anything"));
+  }
+
+  #[test]
+  fn test_sheet_is_selected_with_with_sheets_restriction() {
+    let selected = Some(vec!["Sheet2".to_string()]);
+    assert!(sheet_is_selected(&selected, "Sheet2"));
+    assert!(!sheet_is_selected(&selected, "Sheet1"));
+  }
+
+  #[test]
+  fn test_sheet_heading_carries_sheet_name() {
+    let heading = sheet_heading("Sheet2");
+    assert_eq!(heading.kind, ParagraphKind::Heading(1));
+    assert_eq!(heading.inlines, vec![Inline::Text("Sheet2".to_string())]);
+  }
+
+  #[test]
+  fn test_invalid_merge_region_is_skipped_not_underflowed() {
+    let range = range_from_rows(vec![vec![
+      Data::String("A".into()),
+      Data::String("B".into()),
+    ]]);
+    // A malformed region (end before start) must never reach the colspan/rowspan
+    // subtraction; this would otherwise underflow.
+    // The region starts at B1, a real cell, so it does reach the lookup.
+    let merges: Vec<calamine::Dimensions> = vec![((0, 1), (0, 0))];
+
+    let table = parse_sheet_to_table(&range, &merges, None).unwrap();
+    let row = &table.rows[0];
+    assert_eq!(row.cells.len(), 2);
+    assert_eq!(row.cells[1].colspan.get(), 1);
+    assert_eq!(row.cells[1].rowspan.get(), 1);
+  }
+
+  #[test]
+  fn test_oversized_merge_region_is_rejected_not_walked() {
+    let range = range_from_rows(vec![vec![
+      Data::String("A".into()),
+      Data::String("B".into()),
+    ]]);
+    // A crafted region far larger than the sheet must be rejected rather than
+    // driving the occupancy loop over billions of cells.
+    let merges: Vec<calamine::Dimensions> = vec![((0, 0), (2_000_000_000, 2_000_000_000))];
+
+    let table = parse_sheet_to_table(&range, &merges, None).unwrap();
+    let row = &table.rows[0];
+    assert_eq!(row.cells.len(), 2);
+    assert_eq!(row.cells[0].colspan.get(), 1);
+    assert_eq!(row.cells[0].rowspan.get(), 1);
+  }
+
+  #[test]
+  fn test_content_based_col_width_hint_sums_to_100() {
+    let range = range_from_rows(vec![
+      vec![Data::String("Name".into()), Data::String("A very long description column".into())],
+      vec![Data::String("Alice".into()), Data::String("short".into())],
+    ]);
+
+    let widths = content_based_col_width_hint(&range).unwrap();
+    assert_eq!(widths.len(), 2);
+    assert_eq!(widths.iter().sum::<u32>(), 100);
+    // The second column's widest value is much longer, so it should dominate.
+    assert!(widths[1] > widths[0]);
+  }
+
+  #[test]
+  fn test_content_based_col_width_hint_none_when_empty() {
+    let range = range_from_rows(vec![vec![Data::Empty, Data::Empty]]);
+    assert_eq!(content_based_col_width_hint(&range), None);
+  }
+
+  #[test]
+  fn test_content_based_col_width_hint_drift_correction_favors_largest_column() {
+    // Lengths chosen so naive rounding doesn't sum to exactly 100:
+    // 1/6, 1/6 and 4/6 round to 17 + 17 + 67 = 101.
+    let range = range_from_rows(vec![vec![
+      Data::String("a".into()),
+      Data::String("b".into()),
+      Data::String("abcd".into()),
+    ]]);
+
+    let widths = content_based_col_width_hint(&range).unwrap();
+    assert_eq!(widths.iter().sum::<u32>(), 100);
+    assert_eq!(widths, vec![17, 17, 66]);
+  }
 }
@@ -0,0 +1,351 @@
+use crate::document::model::*;
+use crate::document::providers::DocumentProvider;
+use scraper::{ElementRef, Html, Selector};
+use std::error::Error;
+use std::num::NonZeroU32;
+
+pub struct HtmlTableProvider;
+
+impl HtmlTableProvider {
+  pub fn new() -> Self {
+    Self
+  }
+}
+
+impl DocumentProvider for HtmlTableProvider {
+  fn parse_buffer(&self, data: &[u8]) -> Result<Document, Box<dyn Error + Send + Sync>> {
+    let text = String::from_utf8_lossy(data);
+    let document = Html::parse_document(&text);
+    let table_selector = Selector::parse("table").unwrap();
+
+    let mut blocks: Vec<Block> = Vec::new();
+    for table_el in document.select(&table_selector) {
+      if let Some(table) = parse_table_element(&table_el) {
+        blocks.push(Block::Table(table));
+      }
+    }
+
+    Ok(Document {
+      blocks,
+      metadata: DocumentMetadata::default(),
+      notes: Vec::new(),
+      comments: Vec::new(),
+    })
+  }
+
+  fn name(&self) -> &'static str {
+    "html_table"
+  }
+}
+
+fn parse_table_element(table_el: &ElementRef) -> Option<Table> {
+  let mut table_rows: Vec<TableRow> = Vec::new();
+  let mut has_data = false;
+
+  // Walk the table's own structure only (direct children), not `.select()`,
+  // so a nested `<table>` inside a cell doesn't get its rows pulled into the
+  // outer table — the nested table is still parsed, but as its own Table
+  // block when the outer `document.select("table")` loop reaches it.
+  for child in direct_children_named(table_el, "thead") {
+    for row_el in direct_children_named(&child, "tr") {
+      if let Some(row) = parse_row(&row_el, TableRowKind::Header) {
+        has_data = has_data || row_has_data(&row);
+        table_rows.push(row);
+      }
+    }
+  }
+
+  let tbodies = direct_children_named(table_el, "tbody");
+  if !tbodies.is_empty() {
+    // The HTML parser wraps loose <tr>s in an implicit <tbody>, so rows here
+    // are still classified one by one: a row with <th> cells is a Header.
+    for tbody_el in &tbodies {
+      for row_el in direct_children_named(tbody_el, "tr") {
+        let kind = if row_is_header(&row_el) {
+          TableRowKind::Header
+        } else {
+          TableRowKind::Body
+        };
+        if let Some(row) = parse_row(&row_el, kind) {
+          has_data = has_data || row_has_data(&row);
+          table_rows.push(row);
+        }
+      }
+    }
+  } else {
+    // No <tbody> child element: classify each direct <tr> by
+    // whether it's made of <th> cells (Header) or <td> cells (Body).
+    for row_el in direct_children_named(table_el, "tr") {
+      let kind = if row_is_header(&row_el) {
+        TableRowKind::Header
+      } else {
+        TableRowKind::Body
+      };
+      if let Some(row) = parse_row(&row_el, kind) {
+        has_data = has_data || row_has_data(&row);
+        table_rows.push(row);
+      }
+    }
+  }
+
+  // Skip layout tables (no <th>/<td> content at all).
+  if table_rows.is_empty() || !has_data {
+    return None;
+  }
+
+  Some(Table {
+    rows: table_rows,
+    col_widths: None,
+  })
+}
+
+fn parse_row(row_el: &ElementRef, kind: TableRowKind) -> Option<TableRow> {
+  let cells: Vec<TableCell> = direct_child_elements(row_el)
+    .filter(|el| matches!(el.value().name(), "th" | "td"))
+    .map(|cell_el| parse_cell(&cell_el))
+    .collect();
+
+  if cells.is_empty() {
+    None
+  } else {
+    Some(TableRow { cells, kind })
+  }
+}
+
+fn row_is_header(row_el: &ElementRef) -> bool {
+  direct_child_elements(row_el).any(|el| el.value().name() == "th")
+}
+
+fn parse_cell(cell_el: &ElementRef) -> TableCell {
+  let colspan = cell_el
+    .value()
+    .attr("colspan")
+    .and_then(|v| v.parse::<u32>().ok())
+    .and_then(NonZeroU32::new)
+    .unwrap_or(NonZeroU32::new(1).unwrap());
+
+  let rowspan = cell_el
+    .value()
+    .attr("rowspan")
+    .and_then(|v| v.parse::<u32>().ok())
+    .and_then(NonZeroU32::new)
+    .unwrap_or(NonZeroU32::new(1).unwrap());
+
+  let inlines = parse_inlines(*cell_el);
+  let paragraph = Paragraph {
+    kind: ParagraphKind::Normal,
+    inlines,
+  };
+
+  TableCell {
+    blocks: vec![Block::Paragraph(paragraph)],
+    colspan,
+    rowspan,
+  }
+}
+
+/// Walk a cell's direct children, turning `<a>` into links and `<b>`/`<strong>`
+/// into bold text while flattening everything else down to plain text runs.
+/// Deliberately does not recurse into a nested `<table>` — that table is
+/// parsed separately as its own top-level `Table` block.
+fn parse_inlines(el: ElementRef) -> Vec<Inline> {
+  let mut inlines = Vec::new();
+  for child in el.children() {
+    match child.value() {
+      scraper::node::Node::Text(text) => {
+        if let Some(normalized) = normalize_whitespace(text) {
+          inlines.push(Inline::Text(normalized));
+        }
+      }
+      scraper::node::Node::Element(element) => {
+        let Some(child_el) = ElementRef::wrap(child) else {
+          continue;
+        };
+        match element.name() {
+          "table" => continue,
+          "a" => {
+            let href = element.attr("href").unwrap_or_default().to_string();
+            inlines.push(Inline::Link {
+              href,
+              inlines: parse_inlines(child_el),
+            });
+          }
+          "b" | "strong" => {
+            inlines.push(Inline::Bold(parse_inlines(child_el)));
+          }
+          _ => inlines.extend(parse_inlines(child_el)),
+        }
+      }
+      _ => {}
+    }
+  }
+  inlines
+}
+
+/// Collapse a text node's whitespace runs to a single space, preserving a
+/// leading/trailing space when the original text had one so that adjacent
+/// inline runs (e.g. plain text next to `<b>`/`<a>`) don't get glued
+/// together at word boundaries.
+fn normalize_whitespace(text: &str) -> Option<String> {
+  if text.is_empty() {
+    return None;
+  }
+
+  let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if collapsed.is_empty() {
+    // The run was whitespace-only; it still separates the runs around it.
+    return Some(" ".to_string());
+  }
+
+  let mut result = String::new();
+  if text.starts_with(char::is_whitespace) {
+    result.push(' ');
+  }
+  result.push_str(&collapsed);
+  if text.ends_with(char::is_whitespace) {
+    result.push(' ');
+  }
+  Some(result)
+}
+
+fn row_has_data(row: &TableRow) -> bool {
+  row.cells.iter().any(|cell| {
+    cell.blocks.iter().any(|block| match block {
+      Block::Paragraph(paragraph) => !paragraph.inlines.is_empty(),
+      _ => false,
+    })
+  })
+}
+
+/// Direct (non-descendant) child elements of `el`.
+fn direct_child_elements<'a>(el: &ElementRef<'a>) -> impl Iterator<Item = ElementRef<'a>> {
+  el.children().filter_map(ElementRef::wrap)
+}
+
+/// Direct child elements of `el` whose tag name is `name`.
+fn direct_children_named<'a>(el: &ElementRef<'a>, name: &str) -> Vec<ElementRef<'a>> {
+  direct_child_elements(el)
+    .filter(|child| child.value().name() == name)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(html: &str) -> Document {
+    HtmlTableProvider::new().parse_buffer(html.as_bytes()).unwrap()
+  }
+
+  fn only_table(document: &Document) -> &Table {
+    assert_eq!(document.blocks.len(), 1);
+    match &document.blocks[0] {
+      Block::Table(table) => table,
+      other => panic!("expected a single Table block, got {other:?}"),
+    }
+  }
+
+  fn cell_text(cell: &TableCell) -> String {
+    cell
+      .blocks
+      .iter()
+      .filter_map(|block| match block {
+        Block::Paragraph(p) => Some(p),
+        _ => None,
+      })
+      .flat_map(|p| &p.inlines)
+      .map(|inline| match inline {
+        Inline::Text(t) => t.clone(),
+        Inline::Bold(inner) => inner
+          .iter()
+          .map(|i| if let Inline::Text(t) = i { t.clone() } else { String::new() })
+          .collect::<String>(),
+        Inline::Link { inlines, .. } => inlines
+          .iter()
+          .map(|i| if let Inline::Text(t) = i { t.clone() } else { String::new() })
+          .collect::<String>(),
+        _ => String::new(),
+      })
+      .collect::<Vec<_>>()
+      .join("")
+  }
+
+  #[test]
+  fn test_thead_tbody_table() {
+    let document = parse(
+      "<table><thead><tr><th>Name</th><th>Age</th></tr></thead>\
+       <tbody><tr><td>Alice</td><td>30</td></tr></tbody></table>",
+    );
+    let table = only_table(&document);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].kind, TableRowKind::Header);
+    assert_eq!(table.rows[1].kind, TableRowKind::Body);
+    assert_eq!(cell_text(&table.rows[0].cells[0]), "Name");
+    assert_eq!(cell_text(&table.rows[1].cells[0]), "Alice");
+  }
+
+  #[test]
+  fn test_loose_tr_with_th_is_classified_as_header() {
+    let document = parse("<table><tr><th>Name</th></tr><tr><td>Alice</td></tr></table>");
+    let table = only_table(&document);
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].kind, TableRowKind::Header);
+    assert_eq!(table.rows[1].kind, TableRowKind::Body);
+  }
+
+  #[test]
+  fn test_colspan_and_rowspan_attributes() {
+    let document = parse(
+      "<table><tr><td colspan=\"2\" rowspan=\"3\">Merged</td></tr></table>",
+    );
+    let table = only_table(&document);
+    let cell = &table.rows[0].cells[0];
+    assert_eq!(cell.colspan.get(), 2);
+    assert_eq!(cell.rowspan.get(), 3);
+  }
+
+  #[test]
+  fn test_nested_table_does_not_corrupt_outer_rows() {
+    let document = parse(
+      "<table><tr><td>Outer\
+       <table><tr><td>Inner A</td><td>Inner B</td></tr></table>\
+       </td><td>Second outer cell</td></tr></table>",
+    );
+    // Both the outer and the inner table should be emitted, independently,
+    // each with their own row/cell count.
+    assert_eq!(document.blocks.len(), 2);
+    let outer = match &document.blocks[0] {
+      Block::Table(t) => t,
+      _ => panic!("expected outer table first"),
+    };
+    assert_eq!(outer.rows.len(), 1);
+    assert_eq!(outer.rows[0].cells.len(), 2);
+  }
+
+  #[test]
+  fn test_nested_inline_link_and_bold() {
+    let document = parse(
+      "<table><tr><td><a href=\"https://example.com\">link</a> and <b>bold</b></td></tr></table>",
+    );
+    let table = only_table(&document);
+    assert_eq!(cell_text(&table.rows[0].cells[0]), "link and bold");
+  }
+
+  #[test]
+  fn test_layout_table_with_no_cells_is_skipped() {
+    let document = parse("<table><tr></tr></table>");
+    assert!(document.blocks.is_empty());
+  }
+
+  #[test]
+  fn test_normalize_whitespace_preserves_word_boundaries() {
+    assert_eq!(normalize_whitespace("Hello "), Some("Hello ".to_string()));
+    assert_eq!(normalize_whitespace(" and "), Some(" and ".to_string()));
+    assert_eq!(normalize_whitespace("  "), Some(" ".to_string()));
+    assert_eq!(normalize_whitespace(""), None);
+    assert_eq!(
+      normalize_whitespace("a\n  b   c"),
+      Some("a b c".to_string())
+    );
+  }
+}